@@ -1,26 +1,66 @@
 use bevy::prelude::*;
 use rand::distributions::Uniform;
 use rand::prelude::*;
+use rand::rngs::StdRng;
 use serde::{Deserialize, Serialize};
 use std::collections::{HashSet, VecDeque};
+use std::fs;
 use std::time::Duration;
 use std::mem;
 
-const BOARD_SIZE: u32 = 16;
-const SIZE: f32 = 30.;
-const INIT_LENGTH: usize = 4;
-const SPEED: u64 = 150;
-const DEATH_TIME: u64 = 600;
-const FOOD_BREAK: u64 = 100;
-const FORGIVENESS_BREAK: u64 = 100;
+const SAVE_FILE: &str = "snake_save.ron";
+const CONFIG_FILE: &str = "snake_config.ron";
 
-#[derive(Clone, Copy, Eq, PartialEq, Hash, Properties, Default)]
+/// Runtime-tunable gameplay settings, loaded from `CONFIG_FILE` at startup
+/// (falling back to sensible defaults) so the board size, tick speed,
+/// starting length, and forgiveness timing are data instead of
+/// recompile-time constants.
+#[derive(Clone, Serialize, Deserialize)]
+struct GameConfig {
+    board_size: u32,
+    cell_size: f32,
+    init_length: usize,
+    speed: u64,
+    death_time: u64,
+    food_break: u64,
+    forgiveness_break: u64,
+}
+
+impl Default for GameConfig {
+    fn default() -> Self {
+        Self {
+            board_size: 16,
+            cell_size: 30.,
+            init_length: 4,
+            speed: 150,
+            death_time: 600,
+            food_break: 100,
+            forgiveness_break: 100,
+        }
+    }
+}
+
+fn load_config() -> GameConfig {
+    let mut config: GameConfig = fs::read_to_string(CONFIG_FILE)
+        .ok()
+        .and_then(|contents| ron::from_str(&contents).ok())
+        .unwrap_or_default();
+    if config.board_size % 2 != 0 {
+        // The Hamiltonian cycle the autopilot walks only exists for an even
+        // board_size; round a user-supplied odd size up rather than
+        // relying on `build_hamiltonian_cycle`'s debug-only assertion.
+        config.board_size += 1;
+    }
+    config
+}
+
+#[derive(Clone, Copy, Eq, PartialEq, Hash, Properties, Default, Serialize, Deserialize)]
 struct Pos {
     x: u32,
     y: u32,
 }
 
-#[derive(Clone, Copy, Eq, PartialEq)]
+#[derive(Clone, Copy, Eq, PartialEq, Serialize, Deserialize)]
 enum Dir {
     Up,
     Down,
@@ -28,7 +68,7 @@ enum Dir {
     Right,
 }
 
-#[derive(Clone, Eq, PartialEq)]
+#[derive(Clone, Eq, PartialEq, Serialize, Deserialize)]
 enum SnakeState {
     Alive,
     Dead,
@@ -36,19 +76,104 @@ enum SnakeState {
     Pause(Box<SnakeState>),
 }
 
+/// A plain, serializable snapshot of a game in progress: the body in
+/// head-to-tail order, the pending direction buffer, the score, the
+/// `SnakeState`, and the RNG seed plus the number of `Pos::randomize` draws
+/// already made from it, so the snake and the RNG restore exactly and a run
+/// can be replayed deterministically for debugging. Food entities on the
+/// board are not part of the snapshot; restoring leaves them as they are.
+#[derive(Clone, Serialize, Deserialize)]
+struct SnakeSnapshot {
+    body: Vec<Pos>,
+    dir: VecDeque<Dir>,
+    score: usize,
+    snake_state: SnakeState,
+    seed: u64,
+    rng_draws: u64,
+}
+
 struct GameState {
     head: usize,
     body: Vec<Entity>,
     body_pos_set: HashSet<Pos>,
     dir: VecDeque<Dir>,
     body_material: Handle<ColorMaterial>,
+    food_material: Handle<ColorMaterial>,
     snake_state: SnakeState,
+    cycle: Vec<u32>,
+    autopilot: bool,
+    rng: StdRng,
+    seed: u64,
+    /// Number of `Pos::randomize` draws consumed from `rng` since it was
+    /// seeded, so a snapshot can fast-forward a freshly re-seeded RNG back
+    /// to the exact point it was at when saved.
+    rng_draws: u64,
 }
 
 impl GameState {
     #[inline]
-    pub fn score(&self) -> usize {
-        self.body.len() - INIT_LENGTH
+    pub fn score(&self, config: &GameConfig) -> usize {
+        self.body.len() - config.init_length
+    }
+
+    pub fn to_snapshot(
+        &self,
+        body_query: &Query<(&mut Transform, &mut Pos)>,
+        config: &GameConfig,
+    ) -> SnakeSnapshot {
+        let len = self.body.len();
+        let body = (0..len)
+            .map(|i| {
+                let idx = (self.head + len - i) % len;
+                *body_query.get::<Pos>(self.body[idx]).unwrap()
+            })
+            .collect();
+        SnakeSnapshot {
+            body,
+            dir: self.dir.clone(),
+            score: self.score(config),
+            snake_state: self.snake_state.clone(),
+            seed: self.seed,
+            rng_draws: self.rng_draws,
+        }
+    }
+
+    pub fn from_snapshot(
+        &mut self,
+        snapshot: SnakeSnapshot,
+        commands: &mut Commands,
+        config: &GameConfig,
+    ) {
+        for &entity in &self.body {
+            commands.despawn(entity);
+        }
+        self.body.clear();
+        self.body_pos_set.clear();
+
+        for &pos in snapshot.body.iter().rev() {
+            commands
+                .spawn(SpriteComponents {
+                    material: self.body_material,
+                    sprite: Sprite::new(Vec2::splat(config.cell_size)),
+                    transform: Transform::from_translation(pos.to_world(2.0, config)),
+                    ..Default::default()
+                })
+                .with(pos);
+            self.body.push(commands.current_entity().unwrap());
+            self.body_pos_set.insert(pos);
+        }
+
+        self.head = self.body.len() - 1;
+        self.dir = snapshot.dir;
+        self.snake_state = snapshot.snake_state;
+        self.seed = snapshot.seed;
+        self.rng = StdRng::seed_from_u64(snapshot.seed);
+        self.rng_draws = 0;
+        let mut scratch = Pos::default();
+        for _ in 0..snapshot.rng_draws {
+            scratch.randomize(&mut self.rng, config);
+        }
+        self.rng_draws = snapshot.rng_draws;
     }
 }
 
@@ -57,18 +182,18 @@ impl Pos {
         Self { x, y }
     }
 
-    pub fn to_world(&self, z: f32) -> Vec3 {
+    pub fn to_world(&self, z: f32, config: &GameConfig) -> Vec3 {
         Vec3::new(
-            (self.x as f32 - BOARD_SIZE as f32 / 2.0) * SIZE,
-            (self.y as f32 - BOARD_SIZE as f32 / 2.0) * SIZE,
+            (self.x as f32 - config.board_size as f32 / 2.0) * config.cell_size,
+            (self.y as f32 - config.board_size as f32 / 2.0) * config.cell_size,
             z,
         )
     }
 
-    pub fn update(&mut self, dir: Dir) -> bool {
+    pub fn update(&mut self, dir: Dir, config: &GameConfig) -> bool {
         match dir {
             Dir::Right => {
-                if self.x == BOARD_SIZE - 1 {
+                if self.x == config.board_size - 1 {
                     true
                 } else {
                     self.x += 1;
@@ -76,7 +201,7 @@ impl Pos {
                 }
             }
             Dir::Up => {
-                if self.y == BOARD_SIZE - 1 {
+                if self.y == config.board_size - 1 {
                     true
                 } else {
                     self.y += 1;
@@ -102,12 +227,160 @@ impl Pos {
         }
     }
 
-    pub fn randomize(&mut self) {
-        let mut rng = rand::thread_rng();
-        let distr = Uniform::from(0..BOARD_SIZE);
+    pub fn randomize(&mut self, rng: &mut StdRng, config: &GameConfig) {
+        let distr = Uniform::from(0..config.board_size);
         self.x = rng.sample(distr);
         self.y = rng.sample(distr)
     }
+
+    #[inline]
+    fn cycle_index(&self, config: &GameConfig) -> usize {
+        (self.y * config.board_size + self.x) as usize
+    }
+}
+
+/// Finds a free cell via bounded rejection sampling against `is_occupied`,
+/// falling back to a deterministic pick among the cells that are actually
+/// free once `board_size*board_size` draws have all missed, so this can
+/// never spin forever even when the board is nearly full; when no cell is
+/// free at all, the last drawn (occupied) cell is returned. Returns the cell
+/// and how many `Pos::randomize` draws were consumed (capped at the board
+/// area), so callers that replay RNG draws for deterministic resume (see
+/// `GameState::rng_draws`) see exactly that many `Pos::randomize`-shaped
+/// draws regardless of which branch placed the cell.
+fn place_food(rng: &mut StdRng, config: &GameConfig, is_occupied: impl Fn(Pos) -> bool) -> (Pos, u32) {
+    let total = (config.board_size * config.board_size) as usize;
+    let mut pos = Pos::default();
+    for attempt in 0..total {
+        pos.randomize(rng, config);
+        if !is_occupied(pos) {
+            return (pos, attempt as u32 + 1);
+        }
+    }
+    let free: Vec<Pos> = (0..config.board_size)
+        .flat_map(|x| (0..config.board_size).map(move |y| Pos::new(x, y)))
+        .filter(|&p| !is_occupied(p))
+        .collect();
+    let pos = match free.is_empty() {
+        true => pos,
+        false => free[(pos.x as usize * config.board_size as usize + pos.y as usize) % free.len()],
+    };
+    (pos, total as u32)
+}
+
+/// Builds a Hamiltonian cycle over the board, stored as each cell's index
+/// along the cycle (`cycle[pos.cycle_index()]`). Column 0 is kept as a
+/// straight return lane down to row 0; the remaining columns are swept in
+/// a boustrophedon pattern below the top row, and the top row then carries
+/// the path from the last swept column back to column 0, closing the loop.
+/// Only valid for an even `config.board_size`.
+fn build_hamiltonian_cycle(config: &GameConfig) -> Vec<u32> {
+    debug_assert_eq!(
+        config.board_size % 2,
+        0,
+        "Hamiltonian cycle requires an even board_size"
+    );
+    let n = config.board_size;
+    let mut order = Vec::with_capacity((n * n) as usize);
+
+    order.push(Pos::new(0, 0));
+    for x in 1..n {
+        if x % 2 == 1 {
+            for y in 0..n - 1 {
+                order.push(Pos::new(x, y));
+            }
+        } else {
+            for y in (0..n - 1).rev() {
+                order.push(Pos::new(x, y));
+            }
+        }
+    }
+    for x in (0..n).rev() {
+        order.push(Pos::new(x, n - 1));
+    }
+    for y in (1..n - 1).rev() {
+        order.push(Pos::new(0, y));
+    }
+
+    let mut cycle = vec![0u32; (n * n) as usize];
+    for (i, pos) in order.iter().enumerate() {
+        cycle[pos.cycle_index(config)] = i as u32;
+    }
+    cycle
+}
+
+/// Flood-fills from `start` over cells that aren't occupied by the body,
+/// stopping early once `needed` free cells have been reached so the tail
+/// is guaranteed an escape route.
+fn flood_fill_reaches(
+    start: Pos,
+    body_pos_set: &HashSet<Pos>,
+    needed: usize,
+    config: &GameConfig,
+) -> bool {
+    let mut seen = HashSet::new();
+    let mut stack = vec![start];
+    seen.insert(start);
+    while let Some(pos) = stack.pop() {
+        if needed <= seen.len() {
+            return true;
+        }
+        for &dir in &[Dir::Up, Dir::Down, Dir::Left, Dir::Right] {
+            let mut next = pos;
+            if next.update(dir, config) || body_pos_set.contains(&next) || seen.contains(&next) {
+                continue;
+            }
+            seen.insert(next);
+            stack.push(next);
+        }
+    }
+    needed <= seen.len()
+}
+
+/// Chooses the autopilot's next move. Normally it just follows the
+/// Hamiltonian cycle one step at a time, but it takes a shortcut toward
+/// `food_pos` whenever the shortcut doesn't overtake the tail's position
+/// in cycle order and a flood fill from the candidate cell still reaches
+/// at least `body_len` free cells, guaranteeing the tail stays escapable.
+fn autopilot_dir(
+    cycle: &[u32],
+    head_pos: Pos,
+    tail_pos: Pos,
+    food_pos: Pos,
+    body_pos_set: &HashSet<Pos>,
+    body_len: usize,
+    config: &GameConfig,
+) -> Dir {
+    let total = config.board_size * config.board_size;
+    let head_idx = cycle[head_pos.cycle_index(config)];
+    let ahead = |i: u32| (i + total - head_idx) % total;
+    let tail_ahead = ahead(cycle[tail_pos.cycle_index(config)]);
+    let food_ahead = ahead(cycle[food_pos.cycle_index(config)]);
+
+    let mut fallback = None;
+    let mut shortcut: Option<(u32, Dir)> = None;
+    for &dir in &[Dir::Up, Dir::Down, Dir::Left, Dir::Right] {
+        let mut next = head_pos;
+        if next.update(dir, config) {
+            continue;
+        }
+        if body_pos_set.contains(&next) && next != tail_pos {
+            continue;
+        }
+        let next_ahead = ahead(cycle[next.cycle_index(config)]);
+        if next_ahead == 1 {
+            fallback = Some(dir);
+        }
+        if 1 < next_ahead
+            && next_ahead <= food_ahead
+            && next_ahead < tail_ahead
+            && shortcut.map_or(true, |(best, _)| best < next_ahead)
+            && flood_fill_reaches(next, body_pos_set, body_len, config)
+        {
+            shortcut = Some((next_ahead, dir));
+        }
+    }
+    shortcut.map(|(_, dir)| dir).or(fallback).unwrap_or(Dir::Right)
 }
 
 #[derive(Clone, Copy, Eq, PartialEq, Serialize, Deserialize, Property)]
@@ -140,6 +413,7 @@ fn setup(
     asset_server: Res<AssetServer>,
     mut materials: ResMut<Assets<ColorMaterial>>,
     mut state: ResMut<GameState>,
+    config: Res<GameConfig>,
 ) {
     let font_handle = asset_server.load("assets/fonts/FiraSans-Bold.ttf").unwrap();
     commands
@@ -164,17 +438,17 @@ fn setup(
 
     // Create materials
     let bg_material = materials.add(Color::BLACK.into());
-    let food_material = materials.add(Color::RED.into());
+    state.food_material = materials.add(Color::RED.into());
     state.body_material = materials.add(Color::WHITE.into());
 
     // Spawn body
-    for i in 0..INIT_LENGTH {
-        let pos = Pos::new(i as u32 + 1, BOARD_SIZE / 2);
+    for i in 0..config.init_length {
+        let pos = Pos::new(i as u32 + 1, config.board_size / 2);
         commands
             .spawn(SpriteComponents {
                 material: state.body_material,
-                sprite: Sprite::new(Vec2::splat(SIZE)),
-                transform: Transform::from_translation(pos.to_world(2.0)),
+                sprite: Sprite::new(Vec2::splat(config.cell_size)),
+                transform: Transform::from_translation(pos.to_world(2.0, &config)),
                 ..Default::default()
             })
             .with(pos);
@@ -183,12 +457,12 @@ fn setup(
     }
 
     // Spawn food
-    let pos = Pos::new(BOARD_SIZE * 3 / 4, BOARD_SIZE / 2);
+    let pos = Pos::new(config.board_size * 3 / 4, config.board_size / 2);
     commands
         .spawn(SpriteComponents {
-            material: food_material,
-            sprite: Sprite::new(Vec2::splat(SIZE)),
-            transform: Transform::from_translation(pos.to_world(1.0)),
+            material: state.food_material,
+            sprite: Sprite::new(Vec2::splat(config.cell_size)),
+            transform: Transform::from_translation(pos.to_world(1.0, &config)),
             ..Default::default()
         })
         .with(pos)
@@ -198,24 +472,42 @@ fn setup(
     commands.spawn(SpriteComponents {
         material: bg_material,
         sprite: Sprite::new(Vec2::new(
-            SIZE * BOARD_SIZE as f32,
-            SIZE * BOARD_SIZE as f32,
+            config.cell_size * config.board_size as f32,
+            config.cell_size * config.board_size as f32,
         )),
-        transform: Transform::from_translation(Vec2::splat(-SIZE / 2.0).extend(0.0)),
+        transform: Transform::from_translation(Vec2::splat(-config.cell_size / 2.0).extend(0.0)),
         ..Default::default()
     });
 }
 
-fn update(
+fn movement(
     mut commands: Commands,
     mut state: ResMut<GameState>,
     input: Res<Input<KeyCode>>,
     mut timer: ResMut<UpdateTimer>,
     time: Res<Time>,
     body_query: Query<(&mut Transform, &mut Pos)>,
-    mut food_query: Query<(&mut Transform, &mut Pos, &Tag)>,
-    mut text_query: Query<(&mut Text, &Tag)>,
+    food_query: Query<(&mut Transform, &mut Pos, &Tag)>,
+    mut move_signal: ResMut<MoveSignal>,
+    mut game_over_events: ResMut<Events<GameOverEvent>>,
+    config: Res<GameConfig>,
 ) {
+    move_signal.moved = false;
+
+    if input.just_pressed(KeyCode::F5) {
+        let snapshot = state.to_snapshot(&body_query, &config);
+        if let Ok(serialized) = ron::to_string(&snapshot) {
+            let _ = fs::write(SAVE_FILE, serialized);
+        }
+    }
+    if input.just_pressed(KeyCode::F9) {
+        if let Ok(contents) = fs::read_to_string(SAVE_FILE) {
+            if let Ok(snapshot) = ron::from_str::<SnakeSnapshot>(&contents) {
+                state.from_snapshot(snapshot, &mut commands, &config);
+            }
+        }
+    }
+
     if let SnakeState::Pause(ref prev) = state.snake_state {
         if input.just_pressed(KeyCode::Space) {
             state.snake_state = (**prev).clone();
@@ -227,14 +519,49 @@ fn update(
         state.snake_state = SnakeState::Pause(Box::new(prev));
         return;
     }
+    if input.just_pressed(KeyCode::H) {
+        state.autopilot = !state.autopilot;
+    }
     timer.0.tick(time.delta_seconds);
 
     if matches!(state.snake_state, SnakeState::Alive | SnakeState::Forgive) {
         // Push to the direction buffer
         let prev_dir = *state.dir.back().unwrap();
-        let mut new_dir = None;
+        let mut new_dir = if state.autopilot {
+            let head_pos = *body_query.get::<Pos>(state.body[state.head]).unwrap();
+            let tail = (state.head + 1) % state.body.len();
+            let tail_pos = *body_query.get::<Pos>(state.body[tail]).unwrap();
+            // With multiple food entities on the board, target whichever is
+            // closest in cycle order rather than whichever the query last
+            // happened to enumerate.
+            let total = config.board_size * config.board_size;
+            let head_idx = state.cycle[head_pos.cycle_index(&config)];
+            let mut food_pos = head_pos;
+            let mut food_ahead = total;
+            for (_, pos, tag) in &mut food_query.iter() {
+                if *tag == Tag::food() {
+                    let ahead = (state.cycle[pos.cycle_index(&config)] + total - head_idx) % total;
+                    if ahead < food_ahead {
+                        food_ahead = ahead;
+                        food_pos = *pos;
+                    }
+                }
+            }
+            let dir = autopilot_dir(
+                &state.cycle,
+                head_pos,
+                tail_pos,
+                food_pos,
+                &state.body_pos_set,
+                state.body.len(),
+                &config,
+            );
+            if dir == prev_dir { None } else { Some(dir) }
+        } else {
+            None
+        };
         match prev_dir {
-            Dir::Up | Dir::Down => {
+            Dir::Up | Dir::Down if !state.autopilot => {
                 let mut dx = 0;
                 if input.just_pressed(KeyCode::Left) || input.just_pressed(KeyCode::A) {
                     dx -= 1;
@@ -249,7 +576,7 @@ fn update(
                     new_dir = Some(Dir::Right);
                 }
             }
-            Dir::Right | Dir::Left => {
+            Dir::Right | Dir::Left if !state.autopilot => {
                 let mut dy = 0;
                 if input.just_pressed(KeyCode::Down) || input.just_pressed(KeyCode::S) {
                     dy -= 1;
@@ -264,6 +591,7 @@ fn update(
                     new_dir = Some(Dir::Up);
                 }
             }
+            _ => {}
         }
         if let Some(new_dir) = new_dir {
             state.dir.push_back(new_dir);
@@ -276,7 +604,7 @@ fn update(
     }
 
     if timer.0.finished {
-        timer.0.duration = Duration::from_millis(SPEED).as_secs_f32();
+        timer.0.duration = Duration::from_millis(config.speed).as_secs_f32();
         if 1 < state.dir.len() {
             state.dir.pop_front();
         }
@@ -286,60 +614,26 @@ fn update(
         let head = state.body[state.head];
 
         let mut head_pos = *body_query.get::<Pos>(prev_head).unwrap();
-        if head_pos.update(dir)
+        if head_pos.update(dir, &config)
             || (!state.body_pos_set.insert(head_pos)
                 && *body_query.get::<Pos>(head).unwrap() != head_pos)
         {
             match state.snake_state {
                 SnakeState::Alive => {
-                    timer.0.duration = Duration::from_millis(FORGIVENESS_BREAK).as_secs_f32();
+                    timer.0.duration = Duration::from_millis(config.forgiveness_break).as_secs_f32();
                     timer.0.reset();
                     state.snake_state = SnakeState::Forgive;
                     state.head = (state.body.len() + state.head - 1) % state.body.len();
                     return;
                 }
                 SnakeState::Forgive => {
-                    die(commands, state, timer, body_query, food_query);
+                    game_over_events.send(GameOverEvent);
                     return;
                 }
                 _ => unreachable!(),
             }
         }
 
-        for (mut text, tag) in &mut text_query.iter() {
-            if *tag == Tag::score_text() {
-                text.value = format!("Score: {}", state.score()); // .into();
-            }
-        }
-
-        let mut ate = false;
-        for (mut food_transform, mut food_pos, tag) in &mut food_query.iter() {
-            if *tag == Tag::food() && *food_pos == head_pos {
-                loop {
-                    food_pos.randomize();
-                    if !state.body_pos_set.contains(&food_pos) {
-                        break;
-                    }
-                }
-                food_transform.set_translation(food_pos.to_world(1.0));
-                ate = true;
-            }
-        }
-        if ate {
-            timer.0.duration = Duration::from_millis(FOOD_BREAK).as_secs_f32();
-            let tail = (state.head + 1) % state.body.len();
-            let pos = *body_query.get::<Pos>(state.body[tail]).unwrap();
-            commands
-                .spawn(SpriteComponents {
-                    material: state.body_material,
-                    transform: Transform::from_translation(pos.to_world(2.0)),
-                    sprite: Sprite::new(Vec2::splat(SIZE)),
-                    ..Default::default()
-                })
-                .with(pos);
-            state.body.insert(tail, commands.current_entity().unwrap());
-        }
-
         // Update
         let mut head_pos_ref = body_query.get_mut::<Pos>(head).unwrap();
         state.body_pos_set.remove(&head_pos_ref);
@@ -349,71 +643,427 @@ fn update(
         body_query
             .get_mut::<Transform>(head)
             .unwrap()
-            .set_translation(head_pos.to_world(2.0));
+            .set_translation(head_pos.to_world(2.0, &config));
 
         state.snake_state = SnakeState::Alive;
+        move_signal.moved = true;
+        move_signal.head_pos = head_pos;
+    }
+}
+
+fn eating(
+    mut commands: Commands,
+    move_signal: Res<MoveSignal>,
+    food_query: Query<(Entity, &Pos, &Tag)>,
+    mut eat_events: ResMut<Events<EatEvent>>,
+) {
+    if !move_signal.moved {
+        return;
+    }
+    for (entity, pos, tag) in &mut food_query.iter() {
+        if *tag == Tag::food() && *pos == move_signal.head_pos {
+            commands.despawn(entity);
+            eat_events.send(EatEvent);
+        }
+    }
+}
+
+fn growth(
+    mut commands: Commands,
+    mut state: ResMut<GameState>,
+    mut timer: ResMut<UpdateTimer>,
+    body_query: Query<(&mut Transform, &mut Pos)>,
+    mut eat_reader: Local<EventReader<EatEvent>>,
+    eat_events: Res<Events<EatEvent>>,
+    mut growth_events: ResMut<Events<GrowthEvent>>,
+    config: Res<GameConfig>,
+) {
+    for _ in eat_reader.iter(&eat_events) {
+        timer.0.duration = Duration::from_millis(config.food_break).as_secs_f32();
+        let tail = (state.head + 1) % state.body.len();
+        let pos = *body_query.get::<Pos>(state.body[tail]).unwrap();
+        commands
+            .spawn(SpriteComponents {
+                material: state.body_material,
+                transform: Transform::from_translation(pos.to_world(2.0, &config)),
+                sprite: Sprite::new(Vec2::splat(config.cell_size)),
+                ..Default::default()
+            })
+            .with(pos);
+        state.body.insert(tail, commands.current_entity().unwrap());
+        growth_events.send(GrowthEvent);
+    }
+}
+
+fn scoring(
+    state: Res<GameState>,
+    mut eat_reader: Local<EventReader<EatEvent>>,
+    eat_events: Res<Events<EatEvent>>,
+    mut text_query: Query<(&mut Text, &Tag)>,
+    config: Res<GameConfig>,
+) {
+    if eat_reader.iter(&eat_events).next().is_none() {
+        return;
+    }
+    for (mut text, tag) in &mut text_query.iter() {
+        if *tag == Tag::score_text() {
+            text.value = format!("Score: {}", state.score(&config)); // .into();
+        }
     }
 }
 
-fn die(
+fn game_over(
     mut commands: Commands,
     mut state: ResMut<GameState>,
     mut timer: ResMut<UpdateTimer>,
     body_query: Query<(&mut Transform, &mut Pos)>,
-    mut food_query: Query<(&mut Transform, &mut Pos, &Tag)>,
+    food_query: Query<(Entity, &Tag)>,
+    mut reader: Local<EventReader<GameOverEvent>>,
+    events: Res<Events<GameOverEvent>>,
+    config: Res<GameConfig>,
 ) {
-    println!("Score: {}", state.score());
+    if reader.iter(&events).next().is_none() {
+        return;
+    }
+    println!("Score: {}", state.score(&config));
     timer.0.reset();
-    timer.0.duration = Duration::from_millis(DEATH_TIME).as_secs_f32();
+    timer.0.duration = Duration::from_millis(config.death_time).as_secs_f32();
 
-    state.head = INIT_LENGTH - 1;
+    state.head = config.init_length - 1;
     state.body_pos_set.clear();
     state.dir.clear();
     state.dir.push_back(Dir::Right);
     state.snake_state = SnakeState::Dead;
-    for _ in INIT_LENGTH..state.body.len() {
+    for _ in config.init_length..state.body.len() {
         commands.despawn(state.body.pop().unwrap());
     }
-    for i in 0..INIT_LENGTH {
-        let pos = Pos::new(i as u32 + 1, BOARD_SIZE / 2);
+    for i in 0..config.init_length {
+        let pos = Pos::new(i as u32 + 1, config.board_size / 2);
         state.body_pos_set.insert(pos);
         let entity = state.body[i];
         body_query
             .get_mut::<Transform>(entity)
             .unwrap()
-            .set_translation(pos.to_world(2.0));
+            .set_translation(pos.to_world(2.0, &config));
         *body_query.get_mut::<Pos>(entity).unwrap() = pos;
     }
 
-    let pos = Pos::new(BOARD_SIZE * 3 / 4, BOARD_SIZE / 2);
-    for (mut food_transform, mut food_pos, tag) in &mut food_query.iter() {
+    for (entity, tag) in &mut food_query.iter() {
         if *tag == Tag::food() {
-            food_transform.set_translation(pos.to_world(1.0));
-            *food_pos = pos;
+            commands.despawn(entity);
         }
     }
+    let pos = Pos::new(config.board_size * 3 / 4, config.board_size / 2);
+    commands
+        .spawn(SpriteComponents {
+            material: state.food_material,
+            sprite: Sprite::new(Vec2::splat(config.cell_size)),
+            transform: Transform::from_translation(pos.to_world(1.0, &config)),
+            ..Default::default()
+        })
+        .with(pos)
+        .with(Tag::food());
+}
+
+/// Fired by the growth system whenever a new body segment was inserted,
+/// so sound/particle/UI hooks can react without touching movement logic.
+struct GrowthEvent;
+
+/// Fired by the eating system when the head lands on a food cell.
+struct EatEvent;
+
+/// Fired by the movement system when a second consecutive collision (past
+/// the one-tick forgiveness grace) means the snake has died.
+struct GameOverEvent;
+
+/// Whether the snake actually advanced a cell this tick, and where the new
+/// head landed, so the eating system only runs on ticks the head moved.
+#[derive(Default)]
+struct MoveSignal {
+    moved: bool,
+    head_pos: Pos,
 }
 
 struct UpdateTimer(Timer);
 
+/// Tuning for the food spawner: how many food entities may exist at once,
+/// and how often the spawner tries to add another.
+struct FoodConfig {
+    max_food: usize,
+    spawn_interval: Duration,
+}
+
+impl Default for FoodConfig {
+    fn default() -> Self {
+        Self {
+            max_food: 3,
+            spawn_interval: Duration::from_secs(5),
+        }
+    }
+}
+
+struct FoodSpawnTimer(Timer);
+
+fn food_spawner(
+    mut commands: Commands,
+    mut state: ResMut<GameState>,
+    food_config: Res<FoodConfig>,
+    time: Res<Time>,
+    mut timer: ResMut<FoodSpawnTimer>,
+    food_query: Query<(&Pos, &Tag)>,
+    config: Res<GameConfig>,
+) {
+    timer.0.tick(time.delta_seconds);
+    if !timer.0.finished {
+        return;
+    }
+
+    let mut occupied = state.body_pos_set.clone();
+    let mut food_count = 0;
+    for (pos, tag) in &mut food_query.iter() {
+        if *tag == Tag::food() {
+            food_count += 1;
+            occupied.insert(*pos);
+        }
+    }
+    if food_config.max_food <= food_count {
+        return;
+    }
+
+    let total = (config.board_size * config.board_size) as usize;
+    if total <= occupied.len() {
+        // Board is full; there's nowhere left to place another food.
+        return;
+    }
+
+    let (pos, draws) = place_food(&mut state.rng, &config, |p| occupied.contains(&p));
+    state.rng_draws += draws as u64;
+    commands
+        .spawn(SpriteComponents {
+            material: state.food_material,
+            sprite: Sprite::new(Vec2::splat(config.cell_size)),
+            transform: Transform::from_translation(pos.to_world(1.0, &config)),
+            ..Default::default()
+        })
+        .with(pos)
+        .with(Tag::food());
+}
+
+/// Outcome of a single [`SnakeSim::step`].
+#[derive(Clone, Copy, Eq, PartialEq, Debug)]
+enum StepResult {
+    Alive,
+    Ate,
+    Dead,
+}
+
+/// A Bevy-free mirror of the movement/collision/growth rules, so the
+/// autopilot can be played out thousands of times a second for benchmarking
+/// or AI training, and so the forgiveness/pause rules can be unit tested
+/// without spawning a window or touching ECS storage. `run_autopilot_benchmark`
+/// is the non-test caller that actually exercises it at this scope; wiring
+/// the live Bevy `movement` system itself through `SnakeSim` is left for a
+/// follow-up, since its ECS body representation (a ring buffer of entities)
+/// and multi-food entities don't map onto `SnakeSim`'s single-food
+/// `VecDeque` without a larger rewrite of `movement`/`growth`/`game_over`.
+struct SnakeSim {
+    config: GameConfig,
+    body: VecDeque<Pos>,
+    food: Pos,
+    forgiving: bool,
+    rng: StdRng,
+}
+
+impl SnakeSim {
+    pub fn new(config: GameConfig, seed: u64) -> Self {
+        let mut rng = StdRng::seed_from_u64(seed);
+        let body: VecDeque<Pos> = (0..config.init_length)
+            .map(|i| Pos::new(i as u32 + 1, config.board_size / 2))
+            .collect();
+        let (food, _) = place_food(&mut rng, &config, |p| body.contains(&p));
+        Self {
+            config,
+            body,
+            food,
+            forgiving: false,
+            rng,
+        }
+    }
+
+    pub fn body(&self) -> &VecDeque<Pos> {
+        &self.body
+    }
+
+    pub fn food(&self) -> Pos {
+        self.food
+    }
+
+    /// Advances the simulation by one tick in direction `dir`, mirroring the
+    /// collision and one-tick forgiveness grace used by the `movement`
+    /// system: the first collision is forgiven (the snake holds its
+    /// position), and only a second consecutive collision is fatal.
+    pub fn step(&mut self, dir: Dir) -> StepResult {
+        let mut head = *self.body.back().unwrap();
+        let tail = *self.body.front().unwrap();
+        let hit_wall = head.update(dir, &self.config);
+        let hit_body = !hit_wall && head != tail && self.body.contains(&head);
+
+        if hit_wall || hit_body {
+            return if mem::replace(&mut self.forgiving, true) {
+                StepResult::Dead
+            } else {
+                StepResult::Alive
+            };
+        }
+        self.forgiving = false;
+
+        let ate = head == self.food;
+        self.body.push_back(head);
+        if ate {
+            let occupied = self.body.clone();
+            let (food, _) = place_food(&mut self.rng, &self.config, |p| occupied.contains(&p));
+            self.food = food;
+        } else {
+            self.body.pop_front();
+        }
+        if ate {
+            StepResult::Ate
+        } else {
+            StepResult::Alive
+        }
+    }
+}
+
+/// Plays `games` headless autopilot games through `SnakeSim`, driving the
+/// same `autopilot_dir` the live `movement` system uses for its `H`-toggled
+/// autopilot, and returns the average final score. This is what lets the
+/// autopilot be evaluated thousands of times a second without spawning a
+/// window (invoked from `main` via the `--benchmark` flag).
+fn run_autopilot_benchmark(config: &GameConfig, seed: u64, games: u32) -> f64 {
+    let cycle = build_hamiltonian_cycle(config);
+    let mut total_score = 0usize;
+    for i in 0..games {
+        let mut sim = SnakeSim::new(config.clone(), seed.wrapping_add(i as u64));
+        loop {
+            let body_pos_set: HashSet<Pos> = sim.body().iter().copied().collect();
+            let dir = autopilot_dir(
+                &cycle,
+                *sim.body().back().unwrap(),
+                *sim.body().front().unwrap(),
+                sim.food(),
+                &body_pos_set,
+                sim.body().len(),
+                config,
+            );
+            if sim.step(dir) == StepResult::Dead {
+                break;
+            }
+        }
+        total_score += sim.body().len() - config.init_length;
+    }
+    total_score as f64 / games as f64
+}
+
+#[cfg(test)]
+mod sim_tests {
+    use super::*;
+
+    fn small_config() -> GameConfig {
+        GameConfig {
+            board_size: 8,
+            init_length: 3,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn wall_collision_is_forgiven_once_then_fatal() {
+        let mut sim = SnakeSim::new(small_config(), 0);
+        // Body starts at x = 1..=3 on an 8-wide board, so heading right
+        // reaches the x == 7 wall after four steps.
+        for _ in 0..4 {
+            assert_eq!(sim.step(Dir::Right), StepResult::Alive);
+        }
+        assert_eq!(sim.step(Dir::Right), StepResult::Alive); // forgiven
+        assert_eq!(sim.step(Dir::Right), StepResult::Dead);
+    }
+
+    #[test]
+    fn self_collision_is_forgiven_once_then_fatal() {
+        let mut sim = SnakeSim::new(small_config(), 0);
+        // Turn up and immediately back down, straight into the neck.
+        assert_eq!(sim.step(Dir::Up), StepResult::Alive);
+        assert_eq!(sim.step(Dir::Down), StepResult::Alive); // forgiven
+        assert_eq!(sim.step(Dir::Down), StepResult::Dead);
+    }
+
+    #[test]
+    fn eating_grows_the_body_and_moves_the_food() {
+        let mut sim = SnakeSim::new(small_config(), 0);
+        let before_len = sim.body.len();
+        sim.food = Pos::new(sim.body.back().unwrap().x + 1, sim.body.back().unwrap().y);
+        let food = sim.food;
+        let result = sim.step(Dir::Right);
+        assert_eq!(result, StepResult::Ate);
+        assert_eq!(sim.body.len(), before_len + 1);
+        assert_ne!(sim.food, food);
+    }
+}
+
 fn main() {
+    let seed = rand::thread_rng().gen();
+    let food_config = FoodConfig::default();
+    let game_config = load_config();
+
+    if std::env::args().any(|arg| arg == "--benchmark") {
+        let avg_score = run_autopilot_benchmark(&game_config, seed, 1000);
+        println!("Average autopilot score over 1000 headless games: {:.2}", avg_score);
+        return;
+    }
+
+    let window_size = (game_config.board_size as f32 * game_config.cell_size) as u32;
+
     App::build()
+        .add_resource(WindowDescriptor {
+            width: window_size,
+            height: window_size,
+            title: "Snake".to_string(),
+            ..Default::default()
+        })
         .add_default_plugins()
         .add_resource(GameState {
-            head: INIT_LENGTH - 1,
-            body: Vec::with_capacity(INIT_LENGTH),
+            head: game_config.init_length - 1,
+            body: Vec::with_capacity(game_config.init_length),
             body_pos_set: HashSet::new(),
             dir: vec![Dir::Right].into(),
             body_material: Handle::new(),
+            food_material: Handle::new(),
             snake_state: SnakeState::Dead,
+            cycle: build_hamiltonian_cycle(&game_config),
+            autopilot: false,
+            rng: StdRng::seed_from_u64(seed),
+            seed,
+            rng_draws: 0,
         })
         .add_resource(UpdateTimer(Timer::new(
-            Duration::from_millis(DEATH_TIME),
+            Duration::from_millis(game_config.death_time),
             true,
         )))
+        .add_resource(MoveSignal::default())
+        .add_resource(FoodSpawnTimer(Timer::new(food_config.spawn_interval, true)))
+        .add_resource(food_config)
+        .add_resource(game_config)
+        .add_event::<EatEvent>()
+        .add_event::<GrowthEvent>()
+        .add_event::<GameOverEvent>()
         .register_component::<Pos>()
         .register_component::<Tag>()
         .add_startup_system(setup.system())
-        .add_system(update.system())
+        .add_system(movement.system())
+        .add_system(eating.system())
+        .add_system(growth.system())
+        .add_system(scoring.system())
+        .add_system(food_spawner.system())
+        .add_system(game_over.system())
         .run();
 }